@@ -0,0 +1,47 @@
+//! AEAD backend selection
+//!
+//! The internal [`AeadInPlace`] trait is a small in-place detached AEAD
+//! interface. It is implemented only for the pure-Rust RustCrypto
+//! `XChaCha20Poly1305`, and only the XChaCha20-Poly1305
+//! [`CipherVariant`](super::CipherVariant) arm calls through it; the AES-GCM,
+//! ChaCha20-Poly1305 and ChaCha8-Poly1305 arms do not use it. There is no
+//! `ring` implementation of the trait. Enabling both `backend-ring` and
+//! `backend-rustcrypto` is a compile error; apart from that check, neither
+//! feature changes which implementation a variant uses. The public
+//! [`UdpCipher`](super::UdpCipher) API does not depend on this module.
+
+#[cfg(all(feature = "backend-ring", feature = "backend-rustcrypto"))]
+compile_error!("features `backend-ring` and `backend-rustcrypto` are mutually exclusive");
+
+/// In-place detached AEAD used by the XChaCha20-Poly1305 [`CipherVariant`](super::CipherVariant) arm.
+///
+/// `buf` holds the message to protect; the 16-byte Poly1305/GCM tag is kept
+/// detached so callers can place it wherever the packet layout requires.
+pub(super) trait AeadInPlace {
+    /// Encrypt `buf` in place, returning the detached authentication tag.
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buf: &mut [u8]) -> [u8; 16];
+
+    /// Verify `tag` and decrypt `buf` in place, returning `false` on auth failure.
+    #[must_use]
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buf: &mut [u8], tag: &[u8]) -> bool;
+}
+
+impl AeadInPlace for chacha20poly1305::XChaCha20Poly1305 {
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buf: &mut [u8]) -> [u8; 16] {
+        use chacha20poly1305::{aead::AeadInPlace, XNonce};
+
+        let tag = self
+            .encrypt_in_place_detached(XNonce::from_slice(nonce), aad, buf)
+            .expect("XChaCha20Poly1305 encrypt");
+        let mut out = [0u8; 16];
+        out.copy_from_slice(tag.as_slice());
+        out
+    }
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buf: &mut [u8], tag: &[u8]) -> bool {
+        use chacha20poly1305::{aead::AeadInPlace, Tag, XNonce};
+
+        self.decrypt_in_place_detached(XNonce::from_slice(nonce), aad, buf, Tag::from_slice(tag))
+            .is_ok()
+    }
+}
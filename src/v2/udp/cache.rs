@@ -0,0 +1,209 @@
+//! Session-keyed UDP cipher cache
+
+use std::collections::HashMap;
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+use crate::CipherKind;
+
+use super::UdpCipher;
+
+/// Default number of sessions kept alive in a [`UdpCipherManager`].
+pub const DEFAULT_CAPACITY: usize = 512;
+
+/// An LRU cache of [`UdpCipher`]s keyed by `session_id`.
+///
+/// AEAD2022 UDP derives a per-session subkey with BLAKE3 inside
+/// [`UdpCipher::new`]. Re-running that key schedule for every inbound packet of
+/// a long-lived flow throws away state that is identical across the thousands
+/// of packets the session produces. `UdpCipherManager` keeps a constructed
+/// cipher per `session_id` and amortizes the derivation across the whole flow,
+/// evicting the least-recently-used session once `capacity` is reached.
+///
+/// Only the AES-GCM variants run the per-session BLAKE3 schedule; the
+/// ChaCha20/ChaCha8/XChaCha20 variants ignore `session_id` in their `new(key)`,
+/// so for those kinds the manager keeps a single shared cipher rather than one
+/// map entry per session (see [`derives_per_session`]).
+///
+/// The plain [`UdpCipher::new`] path is left untouched for callers that manage
+/// cipher lifetime themselves.
+pub struct UdpCipherManager {
+    kind: CipherKind,
+    key: Box<[u8]>,
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<u64, Entry>,
+    /// Single cipher reused for kinds with no per-session derivation.
+    shared: Option<UdpCipher>,
+}
+
+struct Entry {
+    cipher: UdpCipher,
+    last_used: u64,
+}
+
+impl UdpCipherManager {
+    /// Create a manager that lazily builds [`UdpCipher`]s for `kind`/`key` and
+    /// retains up to [`DEFAULT_CAPACITY`] sessions.
+    pub fn new(kind: CipherKind, key: &[u8]) -> UdpCipherManager {
+        UdpCipherManager::with_capacity(kind, key, DEFAULT_CAPACITY)
+    }
+
+    /// Create a manager retaining up to `capacity` sessions.
+    pub fn with_capacity(kind: CipherKind, key: &[u8], capacity: usize) -> UdpCipherManager {
+        assert!(capacity > 0, "UdpCipherManager capacity must be non-zero");
+        UdpCipherManager {
+            kind,
+            key: key.into(),
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            shared: None,
+        }
+    }
+
+    /// Cipher's kind
+    #[inline(always)]
+    pub fn kind(&self) -> CipherKind {
+        self.kind
+    }
+
+    /// Number of sessions currently cached.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no sessions.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Encrypt a UDP packet with the cipher for `session_id`, constructing and
+    /// inserting it on a miss.
+    pub fn encrypt_packet_for(&mut self, session_id: u64, salt: &[u8], plaintext_in_ciphertext_out: &mut [u8]) {
+        self.cipher_for(session_id)
+            .encrypt_packet(salt, plaintext_in_ciphertext_out)
+    }
+
+    /// Decrypt a UDP packet with the cipher for `session_id`, constructing and
+    /// inserting it on a miss.
+    pub fn decrypt_packet_for(&mut self, session_id: u64, salt: &[u8], ciphertext_in_plaintext_out: &mut [u8]) -> bool {
+        self.cipher_for(session_id)
+            .decrypt_packet(salt, ciphertext_in_plaintext_out)
+    }
+
+    /// Encrypt a batch of UDP packets for `session_id`, constructing and
+    /// inserting the cipher on a miss. The session is looked up once for the
+    /// whole batch, so a long flow pays a single cache probe per `sendmmsg`.
+    pub fn encrypt_packets_for(&mut self, session_id: u64, packets: &mut [(&[u8], &mut [u8])]) {
+        self.cipher_for(session_id).encrypt_packets(packets)
+    }
+
+    /// Decrypt a batch of UDP packets for `session_id`, returning a per-packet
+    /// success mask.
+    pub fn decrypt_packets_for(&mut self, session_id: u64, packets: &mut [(&[u8], &mut [u8])]) -> Vec<bool> {
+        self.cipher_for(session_id).decrypt_packets(packets)
+    }
+
+    fn cipher_for(&mut self, session_id: u64) -> &UdpCipher {
+        if !derives_per_session(self.kind) {
+            // `new(key)` ignores `session_id` for these kinds, so one cipher
+            // serves every session; a per-session map would only grow without
+            // amortizing any key schedule.
+            if self.shared.is_none() {
+                self.shared = Some(UdpCipher::new(self.kind, &self.key, session_id));
+            }
+            return self.shared.as_ref().expect("shared cipher just inserted");
+        }
+
+        self.tick += 1;
+        let tick = self.tick;
+
+        if !self.entries.contains_key(&session_id) {
+            if self.entries.len() >= self.capacity {
+                self.evict_lru();
+            }
+            let cipher = UdpCipher::new(self.kind, &self.key, session_id);
+            self.entries.insert(session_id, Entry { cipher, last_used: tick });
+        }
+
+        let entry = self.entries.get_mut(&session_id).expect("entry just inserted");
+        entry.last_used = tick;
+        &entry.cipher
+    }
+
+    /// Evict the least-recently-used session.
+    ///
+    /// This is an O(n) scan of the live entries rather than an intrusive LRU
+    /// list. With the modest default [`DEFAULT_CAPACITY`] the scan is cheaper
+    /// than maintaining a second ordering structure, and it only runs on the
+    /// insert that overflows capacity, not on the hot lookup path.
+    fn evict_lru(&mut self) {
+        if let Some((&lru, _)) = self.entries.iter().min_by_key(|(_, e)| e.last_used) {
+            self.entries.remove(&lru);
+        }
+    }
+}
+
+/// Whether `kind` derives a distinct per-session subkey, making a per-session
+/// cache entry worthwhile.
+///
+/// Only the AES-GCM AEAD2022 variants run the BLAKE3 session-subkey schedule in
+/// [`UdpCipher::new`]; the other variants ignore `session_id`.
+fn derives_per_session(kind: CipherKind) -> bool {
+    matches!(
+        kind,
+        CipherKind::AEAD2022_BLAKE3_AES_128_GCM | CipherKind::AEAD2022_BLAKE3_AES_256_GCM
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn derives_per_session_only_for_aes_gcm() {
+        assert!(derives_per_session(CipherKind::AEAD2022_BLAKE3_AES_128_GCM));
+        assert!(derives_per_session(CipherKind::AEAD2022_BLAKE3_AES_256_GCM));
+        assert!(!derives_per_session(CipherKind::AEAD2022_BLAKE3_CHACHA20_POLY1305));
+    }
+
+    #[test]
+    fn non_deriving_kind_shares_one_cipher() {
+        let key = [0u8; 32];
+        let mut manager = UdpCipherManager::new(CipherKind::AEAD2022_BLAKE3_CHACHA20_POLY1305, &key);
+        for session_id in 0..8 {
+            let _ = manager.cipher_for(session_id);
+        }
+        // Every session reuses the shared cipher, so the per-session map stays empty.
+        assert!(manager.is_empty());
+    }
+
+    #[test]
+    fn lru_eviction_bounds_cached_sessions() {
+        let key = [0u8; 32];
+        let mut manager =
+            UdpCipherManager::with_capacity(CipherKind::AEAD2022_BLAKE3_AES_256_GCM, &key, 2);
+        for session_id in 0..5 {
+            let _ = manager.cipher_for(session_id);
+        }
+        assert_eq!(manager.len(), 2);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for UdpCipherManager {
+    fn drop(&mut self) {
+        // Wipe the master-key copy, then drop the cached ciphers so the
+        // BLAKE3-derived per-session subkeys they hold are overwritten too.
+        // Each inner cipher implements `ZeroizeOnDrop`, so clearing `entries`
+        // and `shared` here drops them right after the key is wiped; the
+        // implicit field drop that follows would drop them as well.
+        self.key.zeroize();
+        self.entries.clear();
+        self.shared = None;
+    }
+}
@@ -4,18 +4,30 @@ use crate::{CipherCategory, CipherKind};
 
 #[cfg(feature = "v2-extra")]
 pub use self::chacha8_poly1305::Cipher as ChaCha8Poly1305Cipher;
-pub use self::{aes_gcm::Cipher as AesGcmCipher, chacha20_poly1305::Cipher as ChaCha20Poly1305Cipher};
+#[cfg(feature = "v2-extra")]
+pub use self::xchacha20_poly1305::Cipher as XChaCha20Poly1305Cipher;
+pub use self::{
+    aes_gcm::Cipher as AesGcmCipher,
+    cache::{UdpCipherManager, DEFAULT_CAPACITY},
+    chacha20_poly1305::Cipher as ChaCha20Poly1305Cipher,
+};
 
 mod aes_gcm;
+mod backend;
+mod cache;
 mod chacha20_poly1305;
 #[cfg(feature = "v2-extra")]
 mod chacha8_poly1305;
+#[cfg(feature = "v2-extra")]
+mod xchacha20_poly1305;
 
 enum CipherVariant {
     AesGcm(AesGcmCipher),
     ChaCha20Poly1305(ChaCha20Poly1305Cipher),
     #[cfg(feature = "v2-extra")]
     ChaCha8Poly1305(ChaCha8Poly1305Cipher),
+    #[cfg(feature = "v2-extra")]
+    XChaCha20Poly1305(XChaCha20Poly1305Cipher),
 }
 
 impl CipherVariant {
@@ -31,6 +43,10 @@ impl CipherVariant {
             CipherKind::AEAD2022_BLAKE3_CHACHA8_POLY1305 => {
                 CipherVariant::ChaCha8Poly1305(ChaCha8Poly1305Cipher::new(key))
             }
+            #[cfg(feature = "v2-extra")]
+            CipherKind::AEAD2022_BLAKE3_XCHACHA20_POLY1305 => {
+                CipherVariant::XChaCha20Poly1305(XChaCha20Poly1305Cipher::new(key))
+            }
             _ => unreachable!("Cipher {} is not an AEAD 2022 cipher", kind),
         }
     }
@@ -41,6 +57,8 @@ impl CipherVariant {
             CipherVariant::ChaCha20Poly1305(ref c) => c.encrypt_packet(salt, plaintext_in_ciphertext_out),
             #[cfg(feature = "v2-extra")]
             CipherVariant::ChaCha8Poly1305(ref c) => c.encrypt_packet(salt, plaintext_in_ciphertext_out),
+            #[cfg(feature = "v2-extra")]
+            CipherVariant::XChaCha20Poly1305(ref c) => c.encrypt_packet(salt, plaintext_in_ciphertext_out),
         }
     }
 
@@ -50,6 +68,65 @@ impl CipherVariant {
             CipherVariant::ChaCha20Poly1305(ref c) => c.decrypt_packet(salt, ciphertext_in_plaintext_out),
             #[cfg(feature = "v2-extra")]
             CipherVariant::ChaCha8Poly1305(ref c) => c.decrypt_packet(salt, ciphertext_in_plaintext_out),
+            #[cfg(feature = "v2-extra")]
+            CipherVariant::XChaCha20Poly1305(ref c) => c.decrypt_packet(salt, ciphertext_in_plaintext_out),
+        }
+    }
+
+    fn encrypt_packets(&self, packets: &mut [(&[u8], &mut [u8])]) {
+        // Resolve the variant once so the branch is hoisted out of the loop.
+        match *self {
+            CipherVariant::AesGcm(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    c.encrypt_packet(salt, buf);
+                }
+            }
+            CipherVariant::ChaCha20Poly1305(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    c.encrypt_packet(salt, buf);
+                }
+            }
+            #[cfg(feature = "v2-extra")]
+            CipherVariant::ChaCha8Poly1305(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    c.encrypt_packet(salt, buf);
+                }
+            }
+            #[cfg(feature = "v2-extra")]
+            CipherVariant::XChaCha20Poly1305(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    c.encrypt_packet(salt, buf);
+                }
+            }
+        }
+    }
+
+    fn decrypt_packets(&self, packets: &mut [(&[u8], &mut [u8])], mask: &mut Vec<bool>) {
+        mask.clear();
+        mask.reserve(packets.len());
+        match *self {
+            CipherVariant::AesGcm(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    mask.push(c.decrypt_packet(salt, buf));
+                }
+            }
+            CipherVariant::ChaCha20Poly1305(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    mask.push(c.decrypt_packet(salt, buf));
+                }
+            }
+            #[cfg(feature = "v2-extra")]
+            CipherVariant::ChaCha8Poly1305(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    mask.push(c.decrypt_packet(salt, buf));
+                }
+            }
+            #[cfg(feature = "v2-extra")]
+            CipherVariant::XChaCha20Poly1305(ref c) => {
+                for (salt, buf) in packets.iter_mut() {
+                    mask.push(c.decrypt_packet(salt, buf));
+                }
+            }
         }
     }
 }
@@ -90,4 +167,73 @@ impl UdpCipher {
     pub fn decrypt_packet(&self, salt: &[u8], ciphertext_in_plaintext_out: &mut [u8]) -> bool {
         self.cipher.decrypt_packet(salt, ciphertext_in_plaintext_out)
     }
+
+    /// Encrypt a batch of UDP packets in place.
+    ///
+    /// Each `(salt, buffer)` pair is transformed with the existing per-packet
+    /// logic, but the `match` over the cipher variant is resolved once for the
+    /// whole batch rather than per packet, which pairs naturally with vectored
+    /// (`sendmmsg`-style) socket I/O in downstream crates.
+    pub fn encrypt_packets(&self, packets: &mut [(&[u8], &mut [u8])]) {
+        self.cipher.encrypt_packets(packets)
+    }
+
+    /// Decrypt a batch of UDP packets in place, returning a per-packet success
+    /// mask (`true` where authentication succeeded).
+    pub fn decrypt_packets(&self, packets: &mut [(&[u8], &mut [u8])]) -> Vec<bool> {
+        let mut mask = Vec::with_capacity(packets.len());
+        self.cipher.decrypt_packets(packets, &mut mask);
+        mask
+    }
+}
+
+#[cfg(all(test, feature = "v2-extra"))]
+mod test {
+    use super::*;
+
+    const KEY: [u8; 32] = [0x11; 32];
+
+    fn salt(byte: u8) -> [u8; 24] {
+        [byte; 24]
+    }
+
+    fn framed(payload: &[u8]) -> Vec<u8> {
+        let mut buf = payload.to_vec();
+        buf.extend_from_slice(&[0u8; 16]);
+        buf
+    }
+
+    #[test]
+    fn batch_mask_lines_up_with_packets() {
+        let cipher = UdpCipher::new(CipherKind::AEAD2022_BLAKE3_XCHACHA20_POLY1305, &KEY, 1);
+
+        let payloads: [&[u8]; 3] = [b"first", b"second packet", b"third"];
+        let salts: Vec<[u8; 24]> = (0..3).map(|i| salt(i as u8)).collect();
+        let mut buffers: Vec<Vec<u8>> = payloads.iter().map(|p| framed(p)).collect();
+
+        {
+            let mut packets: Vec<(&[u8], &mut [u8])> = salts
+                .iter()
+                .zip(buffers.iter_mut())
+                .map(|(s, b)| (s.as_slice(), b.as_mut_slice()))
+                .collect();
+            cipher.encrypt_packets(&mut packets);
+        }
+
+        // Corrupt only the middle packet; its mask entry must be the only false.
+        buffers[1][0] ^= 0xff;
+
+        let mask = {
+            let mut packets: Vec<(&[u8], &mut [u8])> = salts
+                .iter()
+                .zip(buffers.iter_mut())
+                .map(|(s, b)| (s.as_slice(), b.as_mut_slice()))
+                .collect();
+            cipher.decrypt_packets(&mut packets)
+        };
+
+        assert_eq!(mask, vec![true, false, true]);
+        assert_eq!(&buffers[0][..payloads[0].len()], payloads[0]);
+        assert_eq!(&buffers[2][..payloads[2].len()], payloads[2]);
+    }
 }